@@ -5,24 +5,53 @@ use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
 
+use cpal::traits::{DeviceTrait, HostTrait};
+use kira::manager::backend::cpal::CpalBackendSettings;
 use kira::manager::{backend::DefaultBackend, AudioManager, AudioManagerSettings};
 use kira::sound::streaming::{StreamingSoundData, StreamingSoundHandle};
 use kira::sound::FromFileError;
-use kira::sound::PlaybackState::{Paused, Playing};
-use kira::track::TrackBuilder;
+use kira::sound::PlaybackState::{Paused, Playing, Stopped};
+use kira::track::{TrackBuilder, TrackHandle};
 use kira::tween::Tween;
 use parking_lot::Mutex;
 use parking_lot_mpsc::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
 
 // Message Passer (useful to Nyquist struct)
 struct MessagePasser {
     tx: Sender<(Message, MessageValue)>,
 }
 
+// Fans incoming PlayerEvents out to every subscriber, dropping ones whose receiver hung up
+struct EventBroadcaster {
+    subscribers: Mutex<Vec<Sender<PlayerEvent>>>,
+}
+
+impl EventBroadcaster {
+    fn new() -> Self {
+        Self {
+            subscribers: Mutex::new(vec![]),
+        }
+    }
+
+    fn subscribe(&self) -> Receiver<PlayerEvent> {
+        let (tx, rx) = channel::<PlayerEvent>();
+        self.subscribers.lock().push(tx);
+        rx
+    }
+
+    fn emit(&self, event: PlayerEvent) {
+        let mut subscribers = self.subscribers.lock();
+        subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
+
 // Lib entry point, this object has to stay alive for the lib to function
 pub struct Nyquist {
     pub playlist: Arc<Mutex<Playlist>>,
     message_passer: MessagePasser,
+    events: Arc<EventBroadcaster>,
 }
 
 impl Default for Nyquist {
@@ -33,26 +62,53 @@ impl Default for Nyquist {
 
 impl Nyquist {
     pub fn new() -> Self {
+        Self::new_with_config(NyquistConfig::default())
+    }
+
+    // Like `new`, but lets the caller pick an output device and master-track settings
+    pub fn new_with_config(config: NyquistConfig) -> Self {
         let (tx, rx) = channel::<(Message, MessageValue)>();
         let playlist = create_playlist();
+        let events = Arc::new(EventBroadcaster::new());
 
         let playlist_clone = Arc::clone(&playlist);
-        thread::spawn(move || manager_thread(playlist_clone));
+        let events_clone = Arc::clone(&events);
+        thread::spawn(move || manager_thread(playlist_clone, events_clone, config));
 
         let playlist_clone = Arc::clone(&playlist);
-        thread::spawn(move || receiver_thread(playlist_clone, rx));
+        let events_clone = Arc::clone(&events);
+        thread::spawn(move || receiver_thread(playlist_clone, rx, events_clone));
 
         Self {
             playlist,
             message_passer: MessagePasser { tx },
+            events,
         }
     }
 
+    // Hands out a receiver that streams playback events, so clients don't have to poll
+    pub fn subscribe(&self) -> Receiver<PlayerEvent> {
+        self.events.subscribe()
+    }
+
     pub fn add_to_playlist(&self, track: Track) {
         let mut playlist_guard = self.playlist.lock();
-        playlist_guard.queue.push(track.clone());
-        playlist_guard.playing = Some(track);
-        println!("bazinga")
+        let playlist_mut = playlist_guard.deref_mut();
+        playlist_mut.queue.push(track.clone());
+        let new_index = playlist_mut.queue.len() - 1;
+
+        // Only hijack playback when nothing is playing (the first track added, or a track added
+        // after the queue finished); otherwise additions just extend the queue
+        if playlist_mut.playing.is_none() {
+            playlist_mut.current_index = new_index;
+            playlist_mut.playing = Some(track);
+        }
+
+        // Shuffle order is built once when shuffle turns on, so newly queued tracks need to
+        // be slotted in too, or they'd be unreachable until the order gets rebuilt
+        if playlist_mut.shuffle {
+            insert_into_shuffle_order(playlist_mut, new_index);
+        }
     }
 
     pub fn list(&self) -> Vec<Track> {
@@ -82,6 +138,56 @@ impl Nyquist {
     pub fn set_vol(&self, vol: f64) {
         self.playlist.lock().current_volume = vol;
     }
+
+    // How far ahead of a track ending the manager thread should start decoding the next one
+    pub fn set_preload_lead(&self, lead: Duration) {
+        self.playlist.lock().preload_lead = lead;
+    }
+
+    // How long the outgoing and incoming tracks overlap when transitioning; zero (the
+    // default) keeps the abrupt cut instead of crossfading
+    pub fn set_crossfade(&self, duration: Duration) {
+        self.playlist.lock().crossfade = duration;
+    }
+
+    pub fn next(&self) -> Result<(), SendError<(Message, MessageValue)>> {
+        self.message_passer
+            .tx
+            .send((Message::Skip, MessageValue::none()))
+    }
+
+    pub fn previous(&self) -> Result<(), SendError<(Message, MessageValue)>> {
+        self.message_passer
+            .tx
+            .send((Message::Previous, MessageValue::none()))
+    }
+
+    pub fn set_repeat(&self, mode: RepeatMode) -> Result<(), SendError<(Message, MessageValue)>> {
+        self.message_passer
+            .tx
+            .send((Message::SetRepeat, MessageValue::int(Some(mode as i32))))
+    }
+
+    pub fn set_shuffle(&self, enabled: bool) -> Result<(), SendError<(Message, MessageValue)>> {
+        self.message_passer.tx.send((
+            Message::SetShuffle,
+            MessageValue::int(Some(enabled as i32)),
+        ))
+    }
+
+    pub fn seek_to(&self, position: Duration) -> Result<(), SendError<(Message, MessageValue)>> {
+        self.message_passer.tx.send((
+            Message::Seek,
+            MessageValue::float(position.as_secs_f64()),
+        ))
+    }
+
+    // Seeks by `seconds` relative to the current position, clamped to the track's bounds
+    pub fn seek_relative(&self, seconds: i64) -> Result<(), SendError<(Message, MessageValue)>> {
+        let (duration, current) = self.get_time();
+        let target = (current.as_secs_f64() + seconds as f64).clamp(0.0, duration.as_secs_f64());
+        self.seek_to(Duration::from_secs_f64(target))
+    }
 }
 
 // Track structure representing a single audio track
@@ -97,6 +203,31 @@ pub enum Message {
     PlaybackPause,
     PlaybackResume,
     EffectVolume,
+    Skip,
+    Previous,
+    SetRepeat,
+    SetShuffle,
+    Seek,
+}
+
+// How the queue behaves once it runs out of tracks to advance to
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RepeatMode {
+    Off,
+    One,
+    All,
+}
+
+// State transitions clients can subscribe to instead of polling get_time/get_vol/list
+#[derive(Debug, Clone)]
+pub enum PlayerEvent {
+    TrackStarted(Track),
+    TrackEnded(Track),
+    QueueFinished,
+    Paused,
+    Resumed,
+    VolumeChanged(f64),
+    PositionChanged(Duration),
 }
 
 #[derive(Clone, Debug)]
@@ -140,6 +271,50 @@ impl MessageValue {
     }
 }
 
+// Default lead time before a track ends at which the next track starts decoding,
+// modeled on librespot's PRELOAD_NEXT_TRACK_BEFORE_END_DURATION_MS
+const DEFAULT_PRELOAD_LEAD: Duration = Duration::from_secs(30);
+// Minimum movement between PositionChanged events, so the unthrottled manager loop
+// doesn't flood subscribers with an event every spin
+const POSITION_EVENT_INTERVAL: Duration = Duration::from_millis(250);
+
+// Configuration accepted by `Nyquist::new_with_config`, letting callers pick an output
+// device and master-track settings instead of taking whatever kira defaults to
+#[derive(Default)]
+pub struct NyquistConfig {
+    /// Output device name, matched against `list_output_devices()`. Falls back to the
+    /// host's default device when `None` or when no device matches.
+    pub output_device: Option<String>,
+    pub master_track: TrackBuilder,
+}
+
+// Lists the names of the output devices available on this host, for callers building a
+// `NyquistConfig`
+pub fn list_output_devices() -> Vec<String> {
+    cpal::default_host()
+        .output_devices()
+        .map(|devices| devices.filter_map(|device| device.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+// Looks up an output device by name the way librespot's audio_backend registry does,
+// falling back to the host's default device when no name is given or nothing matches
+fn resolve_output_device(name: Option<&str>) -> Option<cpal::Device> {
+    let host = cpal::default_host();
+
+    if let Some(name) = name {
+        let matched = host.output_devices().ok().and_then(|mut devices| {
+            devices.find(|device| device.name().map(|n| n == name).unwrap_or(false))
+        });
+
+        if matched.is_some() {
+            return matched;
+        }
+    }
+
+    host.default_output_device()
+}
+
 // Playlist structure maintaining the queue of tracks and playback state
 pub struct Playlist {
     pub queue: Vec<Track>,
@@ -148,6 +323,15 @@ pub struct Playlist {
     pub current_duration: Duration,
     pub current_time: Duration,
     pub current_volume: f64,
+    pub current_index: usize,
+    pub repeat_mode: RepeatMode,
+    pub shuffle: bool,
+    shuffle_order: Vec<usize>,
+    preload_lead: Duration,
+    preloaded: Option<(Track, StreamingSoundData<FromFileError>)>,
+    crossfade: Duration,
+    crossfade_out: Option<StreamingSoundHandle<FromFileError>>,
+    last_position_event: Duration,
     sound_handle: Option<StreamingSoundHandle<FromFileError>>,
 }
 
@@ -160,40 +344,291 @@ pub fn create_playlist() -> Arc<Mutex<Playlist>> {
         current_duration: Default::default(),
         current_time: Default::default(),
         current_volume: 100.0,
+        current_index: 0,
+        repeat_mode: RepeatMode::Off,
+        shuffle: false,
+        shuffle_order: vec![],
+        preload_lead: DEFAULT_PRELOAD_LEAD,
+        preloaded: None,
+        crossfade: Duration::ZERO,
+        crossfade_out: None,
+        last_position_event: Duration::ZERO,
         sound_handle: None,
     }))
 }
 
+// Which way the queue is being traversed
+enum Direction {
+    Forward,
+    Backward,
+}
+
+// Builds a freshly shuffled play order over the queue, keeping `avoid` out of the
+// first slot so shuffled playback never repeats a track back-to-back
+fn shuffled_order(len: usize, avoid: Option<usize>) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..len).collect();
+    let mut rng = rand::thread_rng();
+    order.shuffle(&mut rng);
+
+    if let Some(avoid) = avoid
+        && len > 1
+        && order.first() == Some(&avoid)
+    {
+        order.swap(0, 1);
+    }
+
+    order
+}
+
+// Slots a newly queued track into an already-active shuffle order, landing it somewhere
+// after the current position so the already-played prefix is left untouched
+fn insert_into_shuffle_order(playlist: &mut Playlist, index: usize) {
+    let current_position = playlist
+        .shuffle_order
+        .iter()
+        .position(|&i| i == playlist.current_index)
+        .unwrap_or(playlist.shuffle_order.len());
+
+    let insert_at = if current_position >= playlist.shuffle_order.len() {
+        playlist.shuffle_order.len()
+    } else {
+        rand::thread_rng().gen_range(current_position + 1..=playlist.shuffle_order.len())
+    };
+
+    playlist.shuffle_order.insert(insert_at, index);
+}
+
+// Peeks at the track that should play after the current one, without mutating shuffle
+// state. Used only to decide what to preload; never to commit a transition. At a
+// shuffle wrap boundary the real next order doesn't exist yet, so this simply reports
+// "nothing to preload yet" rather than generating (and discarding) a fresh one every tick.
+fn peek_next_index(playlist: &Playlist) -> Option<usize> {
+    let len = playlist.queue.len();
+    if len == 0 {
+        return None;
+    }
+
+    if playlist.repeat_mode == RepeatMode::One {
+        return Some(playlist.current_index);
+    }
+
+    if playlist.shuffle {
+        let position = playlist
+            .shuffle_order
+            .iter()
+            .position(|&index| index == playlist.current_index)?;
+        return playlist.shuffle_order.get(position + 1).copied();
+    }
+
+    let next = playlist.current_index + 1;
+    if next < len {
+        Some(next)
+    } else if playlist.repeat_mode == RepeatMode::All {
+        Some(0)
+    } else {
+        None
+    }
+}
+
+// Determines the queue index that should play next, honoring repeat mode and shuffle.
+// Unlike `peek_next_index`, this commits a shuffle reshuffle when one is needed, so it
+// must only be called when a transition is actually happening.
+//
+// `manual` distinguishes a user-initiated skip from the player naturally reaching the
+// end of a track: repeat-one should replay the track when it finishes on its own, but
+// must not turn `Nyquist::next()` into a permanent no-op.
+fn advance_index(playlist: &mut Playlist, direction: Direction, manual: bool) -> Option<usize> {
+    let len = playlist.queue.len();
+    if len == 0 {
+        return None;
+    }
+
+    if !manual && playlist.repeat_mode == RepeatMode::One && matches!(direction, Direction::Forward)
+    {
+        return Some(playlist.current_index);
+    }
+
+    if playlist.shuffle {
+        return match direction {
+            Direction::Backward => {
+                // Shuffled playback has no history to step back through, so just reshuffle
+                playlist.shuffle_order = shuffled_order(len, Some(playlist.current_index));
+                playlist.shuffle_order.first().copied()
+            }
+            Direction::Forward => {
+                let position = playlist
+                    .shuffle_order
+                    .iter()
+                    .position(|&index| index == playlist.current_index);
+                let next_position = position.map(|p| p + 1).unwrap_or(0);
+
+                if next_position < playlist.shuffle_order.len() {
+                    playlist.shuffle_order.get(next_position).copied()
+                } else if playlist.repeat_mode == RepeatMode::All {
+                    playlist.shuffle_order = shuffled_order(len, Some(playlist.current_index));
+                    playlist.shuffle_order.first().copied()
+                } else {
+                    None
+                }
+            }
+        };
+    }
+
+    match direction {
+        Direction::Forward => {
+            let next = playlist.current_index + 1;
+            if next < len {
+                Some(next)
+            } else if playlist.repeat_mode == RepeatMode::All {
+                Some(0)
+            } else {
+                None
+            }
+        }
+        Direction::Backward => {
+            if playlist.current_index > 0 {
+                Some(playlist.current_index - 1)
+            } else if playlist.repeat_mode == RepeatMode::All {
+                Some(len - 1)
+            } else {
+                Some(0)
+            }
+        }
+    }
+}
+
+// Stops whatever is currently playing and points the playlist at `index`, letting the
+// manager thread pick up the new track on its next iteration. Also stops any in-flight
+// crossfade so a skip/previous mid-fade doesn't leave the outgoing track audible in
+// the background.
+fn jump_to(playlist: &mut Playlist, index: usize) {
+    if let Some(handle) = playlist.sound_handle.as_mut() {
+        handle.stop(Tween::default());
+    }
+    if let Some(handle) = playlist.crossfade_out.as_mut() {
+        handle.stop(Tween::default());
+    }
+
+    playlist.sound_handle = None;
+    playlist.crossfade_out = None;
+    playlist.preloaded = None;
+    playlist.current_index = index;
+    playlist.playing = playlist.queue.get(index).cloned();
+}
+
 // Thread that queues more songs after they are done playing
-fn manager_thread(playlist: Arc<Mutex<Playlist>>) {
-    let mut manager = AudioManager::<DefaultBackend>::new(AudioManagerSettings::default()).unwrap();
-    let kira_track = manager.add_sub_track(TrackBuilder::default()).unwrap();
+fn manager_thread(playlist: Arc<Mutex<Playlist>>, events: Arc<EventBroadcaster>, config: NyquistConfig) {
+    let manager_settings = AudioManagerSettings {
+        backend_settings: CpalBackendSettings {
+            device: resolve_output_device(config.output_device.as_deref()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut manager = AudioManager::<DefaultBackend>::new(manager_settings).unwrap();
+    let kira_track = manager.add_sub_track(config.master_track).unwrap();
 
     loop {
         let mut guard = playlist.lock();
         let playlist_mut = guard.deref_mut();
 
-        let handle_option = &mut playlist_mut.sound_handle;
-        match handle_option {
-            None => {
-                // Check if there is a track to play
-                if let Some(playing) = &playlist_mut.playing {
-                    let sound_data = StreamingSoundData::from_file(&playing.path)
-                        .unwrap()
-                        .output_destination(&kira_track);
+        // Drop the outgoing handle once its crossfade-out has finished
+        let crossfade_out_finished = playlist_mut
+            .crossfade_out
+            .as_ref()
+            .is_some_and(|handle| handle.state() == Stopped);
+        if crossfade_out_finished {
+            playlist_mut.crossfade_out = None;
+        }
+
+        if playlist_mut.sound_handle.is_none() {
+            // Check if there is a track to play
+            if let Some(playing) = playlist_mut.playing.clone() {
+                let sound_data = StreamingSoundData::from_file(&playing.path)
+                    .unwrap()
+                    .output_destination(&kira_track);
+                playlist_mut.current_duration = sound_data.duration();
+
+                let mut handle = manager.play(sound_data).unwrap();
+                // Pause the sound immediately if the playlist is in a paused state
+                if playlist_mut.paused {
+                    handle.pause(Tween::default());
+                }
+
+                playlist_mut.sound_handle = Some(handle);
+                events.emit(PlayerEvent::TrackStarted(playing));
+            }
+        } else if playlist_mut.sound_handle.as_ref().unwrap().state() == Stopped {
+            if let Some(finished) = playlist_mut.playing.clone() {
+                events.emit(PlayerEvent::TrackEnded(finished));
+            }
+
+            if let Some(next_index) = advance_index(playlist_mut, Direction::Forward, false) {
+                let next_track = playlist_mut.queue.get(next_index).cloned();
+
+                // Gapless handoff: if the next track was already decoded, hand it straight
+                // to the manager instead of reopening the file from disk.
+                let reuse_preload = match (&playlist_mut.preloaded, &next_track) {
+                    (Some((preloaded_track, _)), Some(next)) => preloaded_track.path == next.path,
+                    _ => false,
+                };
+
+                let sound_data = if reuse_preload {
+                    playlist_mut.preloaded.take().map(|(_, data)| data)
+                } else {
+                    playlist_mut.preloaded = None;
+                    next_track.as_ref().and_then(|track| {
+                        StreamingSoundData::from_file(&track.path)
+                            .ok()
+                            .map(|data| data.output_destination(&kira_track))
+                    })
+                };
+
+                if let (Some(sound_data), Some(next_track)) = (sound_data, next_track) {
                     playlist_mut.current_duration = sound_data.duration();
+                    playlist_mut.current_time = Duration::default();
 
                     let mut handle = manager.play(sound_data).unwrap();
-                    // Pause the sound immediately if the playlist is in a paused state
                     if playlist_mut.paused {
                         handle.pause(Tween::default());
                     }
 
-                    *handle_option = Some(handle);
+                    playlist_mut.current_index = next_index;
+                    playlist_mut.playing = Some(next_track.clone());
+                    playlist_mut.sound_handle = Some(handle);
+                    events.emit(PlayerEvent::TrackStarted(next_track));
+                } else {
+                    playlist_mut.sound_handle = None;
                 }
+            } else {
+                // Queue exhausted with repeat off: stop and wait for user action
+                playlist_mut.sound_handle = None;
+                playlist_mut.playing = None;
+                playlist_mut.preloaded = None;
+                events.emit(PlayerEvent::QueueFinished);
             }
-            Some(handle) => {
-                playlist_mut.current_time = Duration::from_secs_f64(handle.position());
+        } else {
+            let position = playlist_mut.sound_handle.as_ref().unwrap().position();
+            playlist_mut.current_time = Duration::from_secs_f64(position);
+
+            let moved = if playlist_mut.current_time >= playlist_mut.last_position_event {
+                playlist_mut.current_time - playlist_mut.last_position_event
+            } else {
+                playlist_mut.last_position_event - playlist_mut.current_time
+            };
+            if moved >= POSITION_EVENT_INTERVAL {
+                playlist_mut.last_position_event = playlist_mut.current_time;
+                events.emit(PlayerEvent::PositionChanged(playlist_mut.current_time));
+            }
+
+            let remaining = playlist_mut
+                .current_duration
+                .saturating_sub(playlist_mut.current_time);
+            if remaining < playlist_mut.preload_lead {
+                preload_next_track(playlist_mut, &kira_track);
+            }
+            if playlist_mut.crossfade_out.is_none() && remaining < playlist_mut.crossfade {
+                start_crossfade(playlist_mut, &mut manager, &kira_track, &events);
             }
         }
 
@@ -202,8 +637,94 @@ fn manager_thread(playlist: Arc<Mutex<Playlist>>) {
     }
 }
 
+// Decodes the upcoming track ahead of time so the transition into it is gapless
+fn preload_next_track(playlist: &mut Playlist, kira_track: &TrackHandle) {
+    let Some(next_track) =
+        peek_next_index(playlist).and_then(|index| playlist.queue.get(index).cloned())
+    else {
+        return;
+    };
+
+    let already_preloaded = playlist
+        .preloaded
+        .as_ref()
+        .is_some_and(|(track, _)| track.path == next_track.path);
+    if already_preloaded {
+        return;
+    }
+
+    if let Ok(sound_data) = StreamingSoundData::from_file(&next_track.path) {
+        playlist.preloaded = Some((next_track, sound_data.output_destination(kira_track)));
+    }
+}
+
+// Starts the next track early and crossfades into it: the outgoing handle fades its
+// volume to silence and stops, while the incoming one fades in from silence up to
+// `current_volume`, both over `playlist.crossfade`
+fn start_crossfade(
+    playlist: &mut Playlist,
+    manager: &mut AudioManager<DefaultBackend>,
+    kira_track: &TrackHandle,
+    events: &EventBroadcaster,
+) {
+    let Some(next_index) = advance_index(playlist, Direction::Forward, false) else {
+        return;
+    };
+    let Some(next_track) = playlist.queue.get(next_index).cloned() else {
+        return;
+    };
+
+    let reuse_preload = playlist
+        .preloaded
+        .as_ref()
+        .is_some_and(|(track, _)| track.path == next_track.path);
+
+    let sound_data = if reuse_preload {
+        playlist.preloaded.take().map(|(_, data)| data)
+    } else {
+        StreamingSoundData::from_file(&next_track.path)
+            .ok()
+            .map(|data| data.output_destination(kira_track))
+    };
+
+    let Some(sound_data) = sound_data else {
+        return;
+    };
+    let duration = sound_data.duration();
+
+    let fade = Tween {
+        duration: playlist.crossfade,
+        ..Default::default()
+    };
+
+    let mut incoming = manager.play(sound_data).unwrap();
+    incoming.set_volume(0.0, Tween::default());
+    incoming.set_volume(playlist.current_volume, fade);
+    if playlist.paused {
+        incoming.pause(Tween::default());
+    }
+
+    if let Some(mut outgoing) = playlist.sound_handle.take() {
+        outgoing.stop(fade);
+        playlist.crossfade_out = Some(outgoing);
+    }
+
+    playlist.current_duration = duration;
+    playlist.current_time = Duration::default();
+    playlist.current_index = next_index;
+    playlist.playing = Some(next_track.clone());
+    playlist.sound_handle = Some(incoming);
+    playlist.preloaded = None;
+
+    events.emit(PlayerEvent::TrackStarted(next_track));
+}
+
 // Receiver thread that listens for messages and controls playback
-fn receiver_thread(playlist: Arc<Mutex<Playlist>>, rx: Receiver<(Message, MessageValue)>) {
+fn receiver_thread(
+    playlist: Arc<Mutex<Playlist>>,
+    rx: Receiver<(Message, MessageValue)>,
+    events: Arc<EventBroadcaster>,
+) {
     // Instead of using Tokio::yield(), the iterator of rx automatically blocks this thread until a new message is ready
     // The iterator ends after the channel hungs up
     for (kind, value) in rx.into_iter() {
@@ -217,8 +738,13 @@ fn receiver_thread(playlist: Arc<Mutex<Playlist>>, rx: Receiver<(Message, Messag
                     && handle.state() == Playing
                 {
                     handle.pause(Tween::default());
-                    println!("Playback paused");
                     playlist_mut.paused = true;
+                    events.emit(PlayerEvent::Paused);
+                }
+
+                // Freeze a crossfade in progress too, so it doesn't keep fading while paused
+                if let Some(outgoing) = playlist_mut.crossfade_out.as_mut() {
+                    outgoing.pause(Tween::default());
                 }
             }
             Message::PlaybackResume => {
@@ -230,8 +756,14 @@ fn receiver_thread(playlist: Arc<Mutex<Playlist>>, rx: Receiver<(Message, Messag
                     && handle.state() == Paused
                 {
                     handle.resume(Tween::default());
-                    println!("Playback resumed");
                     playlist_mut.paused = false;
+                    events.emit(PlayerEvent::Resumed);
+                }
+
+                if let Some(outgoing) = playlist_mut.crossfade_out.as_mut()
+                    && outgoing.state() == Paused
+                {
+                    outgoing.resume(Tween::default());
                 }
             }
             Message::EffectVolume => {
@@ -241,9 +773,195 @@ fn receiver_thread(playlist: Arc<Mutex<Playlist>>, rx: Receiver<(Message, Messag
                 if let Some(handle) = playlist_mut.sound_handle.as_mut() {
                     playlist_mut.current_volume = value.float.unwrap();
                     handle.set_volume(playlist_mut.current_volume, Default::default());
+                    events.emit(PlayerEvent::VolumeChanged(playlist_mut.current_volume));
+                }
+            }
+            Message::Skip => {
+                let mut guard = playlist.lock();
+                let playlist_mut = guard.deref_mut();
+
+                if let Some(index) = advance_index(playlist_mut, Direction::Forward, true) {
+                    jump_to(playlist_mut, index);
+                }
+            }
+            Message::Previous => {
+                let mut guard = playlist.lock();
+                let playlist_mut = guard.deref_mut();
+
+                if let Some(index) = advance_index(playlist_mut, Direction::Backward, true) {
+                    jump_to(playlist_mut, index);
+                }
+            }
+            Message::SetRepeat => {
+                let mut guard = playlist.lock();
+                let playlist_mut = guard.deref_mut();
+
+                playlist_mut.repeat_mode = match value.int {
+                    Some(1) => RepeatMode::One,
+                    Some(2) => RepeatMode::All,
+                    _ => RepeatMode::Off,
+                };
+            }
+            Message::SetShuffle => {
+                let mut guard = playlist.lock();
+                let playlist_mut = guard.deref_mut();
+
+                playlist_mut.shuffle = value.int.unwrap_or(0) != 0;
+                if playlist_mut.shuffle {
+                    let len = playlist_mut.queue.len();
+                    playlist_mut.shuffle_order =
+                        shuffled_order(len, Some(playlist_mut.current_index));
+                }
+            }
+            Message::Seek => {
+                let mut guard = playlist.lock();
+                let playlist_mut = guard.deref_mut();
+
+                if let Some(handle) = playlist_mut.sound_handle.as_mut() {
+                    handle.seek_to(value.float.unwrap());
+                    playlist_mut.current_time = Duration::from_secs_f64(handle.position());
                 }
             }
             Message::None | Message::PlaylistUpdated => {}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playlist_with(queue_len: usize, current_index: usize) -> Playlist {
+        let queue: Vec<Track> = (0..queue_len)
+            .map(|i| Track {
+                path: format!("track-{i}.mp3"),
+            })
+            .collect();
+        let playing = queue.get(current_index).cloned();
+
+        Playlist {
+            queue,
+            playing,
+            paused: false,
+            current_duration: Duration::default(),
+            current_time: Duration::default(),
+            current_volume: 100.0,
+            current_index,
+            repeat_mode: RepeatMode::Off,
+            shuffle: false,
+            shuffle_order: vec![],
+            preload_lead: DEFAULT_PRELOAD_LEAD,
+            preloaded: None,
+            crossfade: Duration::ZERO,
+            crossfade_out: None,
+            last_position_event: Duration::ZERO,
+            sound_handle: None,
+        }
+    }
+
+    #[test]
+    fn advance_forward_stops_at_end_with_repeat_off() {
+        let mut playlist = playlist_with(3, 2);
+        assert_eq!(advance_index(&mut playlist, Direction::Forward, false), None);
+    }
+
+    #[test]
+    fn advance_forward_moves_to_next_with_repeat_off() {
+        let mut playlist = playlist_with(3, 0);
+        assert_eq!(advance_index(&mut playlist, Direction::Forward, false), Some(1));
+    }
+
+    #[test]
+    fn advance_backward_clamps_at_start_with_repeat_off() {
+        let mut playlist = playlist_with(3, 0);
+        assert_eq!(advance_index(&mut playlist, Direction::Backward, false), Some(0));
+    }
+
+    #[test]
+    fn advance_wraps_both_directions_with_repeat_all() {
+        let mut forward = playlist_with(3, 2);
+        forward.repeat_mode = RepeatMode::All;
+        assert_eq!(advance_index(&mut forward, Direction::Forward, false), Some(0));
+
+        let mut backward = playlist_with(3, 0);
+        backward.repeat_mode = RepeatMode::All;
+        assert_eq!(advance_index(&mut backward, Direction::Backward, false), Some(2));
+    }
+
+    #[test]
+    fn advance_replays_current_track_on_natural_end_with_repeat_one() {
+        let mut playlist = playlist_with(3, 1);
+        playlist.repeat_mode = RepeatMode::One;
+        assert_eq!(advance_index(&mut playlist, Direction::Forward, false), Some(1));
+    }
+
+    #[test]
+    fn manual_skip_moves_past_repeat_one() {
+        let mut playlist = playlist_with(3, 1);
+        playlist.repeat_mode = RepeatMode::One;
+        assert_eq!(advance_index(&mut playlist, Direction::Forward, true), Some(2));
+    }
+
+    #[test]
+    fn manual_previous_is_unaffected_by_repeat_one() {
+        let mut playlist = playlist_with(3, 1);
+        playlist.repeat_mode = RepeatMode::One;
+        assert_eq!(advance_index(&mut playlist, Direction::Backward, true), Some(0));
+    }
+
+    #[test]
+    fn shuffled_order_never_lands_avoid_in_first_slot() {
+        for _ in 0..50 {
+            let order = shuffled_order(2, Some(0));
+            assert_ne!(order.first(), Some(&0));
+        }
+    }
+
+    #[test]
+    fn peek_next_index_does_not_mutate_shuffle_order() {
+        let mut playlist = playlist_with(3, 0);
+        playlist.shuffle = true;
+        playlist.shuffle_order = vec![0, 1, 2];
+        let before = playlist.shuffle_order.clone();
+
+        assert_eq!(peek_next_index(&playlist), Some(1));
+        assert_eq!(playlist.shuffle_order, before);
+    }
+
+    #[test]
+    fn peek_next_index_reports_none_at_shuffle_wrap_boundary() {
+        let mut playlist = playlist_with(3, 2);
+        playlist.shuffle = true;
+        playlist.shuffle_order = vec![1, 0, 2];
+        assert_eq!(peek_next_index(&playlist), None);
+    }
+
+    #[test]
+    fn jump_to_clears_sound_state_and_crossfade_out() {
+        let mut playlist = playlist_with(3, 0);
+        jump_to(&mut playlist, 2);
+
+        assert_eq!(playlist.current_index, 2);
+        assert_eq!(playlist.playing.as_ref().map(|t| &t.path), Some(&"track-2.mp3".to_string()));
+        assert!(playlist.sound_handle.is_none());
+        assert!(playlist.crossfade_out.is_none());
+        assert!(playlist.preloaded.is_none());
+    }
+
+    #[test]
+    fn insert_into_shuffle_order_preserves_played_prefix() {
+        let mut playlist = playlist_with(3, 1);
+        playlist.shuffle = true;
+        playlist.shuffle_order = vec![2, 1, 0];
+
+        insert_into_shuffle_order(&mut playlist, 3);
+
+        let current_position = playlist
+            .shuffle_order
+            .iter()
+            .position(|&i| i == 1)
+            .unwrap();
+        assert_eq!(&playlist.shuffle_order[..=current_position], &[2, 1]);
+        assert!(playlist.shuffle_order.contains(&3));
+    }
+}